@@ -0,0 +1,168 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+const CONFIG_FILE_NAME: &str = ".codereviewer.toml";
+
+/// Severity of a finding, ordered from least to most severe so it can be compared
+/// against the configured `fail_on` threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Severity {
+    Low,
+    Medium,
+    High,
+}
+
+impl Severity {
+    // Not called in production yet (fail_on is only ever set via config deserialization),
+    // but kept as the textual counterpart to ForgeKind::parse for whenever a --fail-on
+    // CLI override is added; exercised directly by the test below.
+    #[allow(dead_code)]
+    pub(crate) fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "low" => Ok(Severity::Low),
+            "medium" => Ok(Severity::Medium),
+            "high" => Ok(Severity::High),
+            other => anyhow::bail!("Unknown severity: {}", other),
+        }
+    }
+}
+
+/// Project configuration read from `.codereviewer.toml`, modeled on git-next's
+/// `RepoConfig::load` convention of falling back to defaults when the file is absent.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct RepoConfig {
+    /// Minimum severity that causes the process to exit non-zero in `--output json` mode.
+    pub(crate) fail_on: Option<Severity>,
+    /// Glob patterns for paths to exclude from review.
+    pub(crate) ignore: Vec<String>,
+    /// Model to send to the Kimi API, overriding the built-in default.
+    pub(crate) model: Option<String>,
+    /// Sampling temperature to send to the Kimi API.
+    pub(crate) temperature: Option<f32>,
+}
+
+impl RepoConfig {
+    /// Loads `.codereviewer.toml` from `repo_path`, or returns the default config if
+    /// the file doesn't exist.
+    pub(crate) fn load(repo_path: &str) -> Result<Self> {
+        let config_path = Path::new(repo_path).join(CONFIG_FILE_NAME);
+
+        if !config_path.exists() {
+            return Ok(RepoConfig::default());
+        }
+
+        let contents = fs::read_to_string(&config_path)
+            .with_context(|| format!("Failed to read {}", config_path.display()))?;
+
+        toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse {}", config_path.display()))
+    }
+
+    /// Whether `path` matches one of the configured ignore globs.
+    pub(crate) fn is_ignored(&self, path: &str) -> bool {
+        self.ignore.iter().any(|pattern| glob_match(pattern, path))
+    }
+}
+
+/// A small `*`-only glob matcher, sufficient for ignoring path patterns like `tests/*` or
+/// `*.generated.rs`.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let mut segments = pattern.split('*').peekable();
+    let mut rest = text;
+
+    if let Some(first) = segments.peek() {
+        if !pattern.starts_with('*') {
+            match rest.strip_prefix(*first) {
+                Some(r) => rest = r,
+                None => return false,
+            }
+            segments.next();
+        }
+    }
+
+    while let Some(segment) = segments.next() {
+        if segment.is_empty() {
+            continue;
+        }
+        if segments.peek().is_none() && !pattern.ends_with('*') {
+            return rest.ends_with(segment);
+        }
+        match rest.find(segment) {
+            Some(idx) => rest = &rest[idx + segment.len()..],
+            None => return false,
+        }
+    }
+
+    pattern.ends_with('*') || rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn severity_orders_low_to_high() {
+        assert!(Severity::Low < Severity::Medium);
+        assert!(Severity::Medium < Severity::High);
+        assert_eq!(Severity::High.max(Severity::Low), Severity::High);
+    }
+
+    #[test]
+    fn severity_parse_accepts_known_names_case_insensitively() {
+        assert_eq!(Severity::parse("high").unwrap(), Severity::High);
+        assert_eq!(Severity::parse("MEDIUM").unwrap(), Severity::Medium);
+        assert!(Severity::parse("critical").is_err());
+    }
+
+    #[test]
+    fn load_returns_default_when_config_file_is_absent() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let config = RepoConfig::load(&temp_dir.path().to_string_lossy()).unwrap();
+
+        assert!(config.fail_on.is_none());
+        assert!(config.ignore.is_empty());
+    }
+
+    #[test]
+    fn load_parses_an_existing_config_file() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        fs::write(
+            temp_dir.path().join(CONFIG_FILE_NAME),
+            "fail_on = \"high\"\nignore = [\"tests/*\"]\n",
+        )
+        .expect("Failed to write config file");
+
+        let config = RepoConfig::load(&temp_dir.path().to_string_lossy()).unwrap();
+
+        assert_eq!(config.fail_on, Some(Severity::High));
+        assert_eq!(config.ignore, vec!["tests/*".to_string()]);
+    }
+
+    #[test]
+    fn glob_match_handles_prefix_suffix_and_middle_wildcards() {
+        assert!(glob_match("tests/*", "tests/foo.rs"));
+        assert!(glob_match("*.generated.rs", "schema.generated.rs"));
+        assert!(glob_match("src/*/mod.rs", "src/git/mod.rs"));
+        assert!(!glob_match("tests/*", "src/main.rs"));
+        assert!(!glob_match("*.generated.rs", "schema.rs"));
+    }
+
+    #[test]
+    fn is_ignored_checks_all_configured_globs() {
+        let config = RepoConfig {
+            ignore: vec!["tests/*".to_string(), "*.lock".to_string()],
+            ..RepoConfig::default()
+        };
+
+        assert!(config.is_ignored("tests/foo.rs"));
+        assert!(config.is_ignored("Cargo.lock"));
+        assert!(!config.is_ignored("src/main.rs"));
+    }
+}
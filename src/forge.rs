@@ -0,0 +1,166 @@
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde_json::json;
+
+/// A code hosting platform that review results can be posted back to.
+#[async_trait::async_trait]
+pub trait Forge {
+    /// Posts `body` as a review comment against `repo` (`owner/name`) for `sha_or_pr`.
+    async fn post_review(&self, repo: &str, sha_or_pr: &str, body: &str) -> Result<()>;
+}
+
+/// Posts commit comments to GitHub via the REST API.
+pub struct GitHubForge {
+    client: Client,
+    token: String,
+    base_url: String,
+}
+
+impl GitHubForge {
+    pub fn new(token: String) -> Self {
+        GitHubForge {
+            client: Client::new(),
+            token,
+            base_url: "https://api.github.com".to_string(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Forge for GitHubForge {
+    async fn post_review(&self, repo: &str, sha_or_pr: &str, body: &str) -> Result<()> {
+        let url = format!(
+            "{}/repos/{}/commits/{}/comments",
+            self.base_url, repo, sha_or_pr
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Accept", "application/vnd.github+json")
+            .header("User-Agent", "codeReviewer")
+            .json(&json!({ "body": body }))
+            .send()
+            .await
+            .context("Failed to send review comment to GitHub")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("GitHub API request failed with status {}: {}", status, error_text);
+        }
+
+        Ok(())
+    }
+}
+
+/// Posts commit comments to a Forgejo/Gitea instance via its REST API.
+pub struct ForgejoForge {
+    client: Client,
+    token: String,
+    base_url: String,
+}
+
+impl ForgejoForge {
+    pub fn new(token: String, base_url: String) -> Self {
+        ForgejoForge {
+            client: Client::new(),
+            token,
+            base_url,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Forge for ForgejoForge {
+    async fn post_review(&self, repo: &str, sha_or_pr: &str, body: &str) -> Result<()> {
+        let url = format!(
+            "{}/api/v1/repos/{}/commits/{}/comments",
+            self.base_url, repo, sha_or_pr
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("token {}", self.token))
+            .json(&json!({ "body": body }))
+            .send()
+            .await
+            .context("Failed to send review comment to Forgejo")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Forgejo API request failed with status {}: {}", status, error_text);
+        }
+
+        Ok(())
+    }
+}
+
+/// Which forge implementation to use, selected via the `--forge` CLI flag; it is not read
+/// from `.codereviewer.toml`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForgeKind {
+    GitHub,
+    Forgejo,
+}
+
+impl ForgeKind {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "github" => Ok(ForgeKind::GitHub),
+            "forgejo" | "gitea" => Ok(ForgeKind::Forgejo),
+            other => anyhow::bail!("Unknown forge type: {}", other),
+        }
+    }
+}
+
+/// Builds a `Forge` from a kind, a bearer token, and (for self-hosted forges) a base URL.
+pub fn build(kind: ForgeKind, token: String, base_url: Option<String>) -> Result<Box<dyn Forge>> {
+    match kind {
+        ForgeKind::GitHub => Ok(Box::new(GitHubForge::new(token))),
+        ForgeKind::Forgejo => {
+            let base_url = base_url.context("Forgejo forge requires a base URL")?;
+            Ok(Box::new(ForgejoForge::new(token, base_url)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forge_kind_parse_accepts_known_names() {
+        assert_eq!(ForgeKind::parse("github").unwrap(), ForgeKind::GitHub);
+        assert_eq!(ForgeKind::parse("forgejo").unwrap(), ForgeKind::Forgejo);
+    }
+
+    #[test]
+    fn forge_kind_parse_accepts_gitea_as_a_forgejo_alias() {
+        assert_eq!(ForgeKind::parse("gitea").unwrap(), ForgeKind::Forgejo);
+    }
+
+    #[test]
+    fn forge_kind_parse_rejects_unknown_names() {
+        assert!(ForgeKind::parse("bitbucket").is_err());
+    }
+
+    #[test]
+    fn build_requires_a_base_url_for_forgejo() {
+        assert!(build(ForgeKind::Forgejo, "token".to_string(), None).is_err());
+        assert!(build(
+            ForgeKind::Forgejo,
+            "token".to_string(),
+            Some("https://forge.example.com".to_string())
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn build_does_not_require_a_base_url_for_github() {
+        assert!(build(ForgeKind::GitHub, "token".to_string(), None).is_ok());
+    }
+}
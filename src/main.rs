@@ -1,13 +1,17 @@
 use std::env;
 use std::path::Path;
-use std::process::Command;
-use reqwest;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tokio;
 use anyhow::{Result, Context};
 use clap::{Arg, Command as ClapCommand};
 
+mod config;
+mod forge;
+mod git;
+mod server;
+
+use config::{RepoConfig, Severity};
+
 #[derive(Debug, Serialize, Deserialize)]
 struct KimiResponse {
     choices: Vec<Choice>,
@@ -23,111 +27,186 @@ struct Message {
     content: String,
 }
 
-struct GitDiff {
-    file_path: String,
-    content: String,
+pub(crate) struct GitDiff {
+    pub(crate) file_path: std::path::PathBuf,
+    pub(crate) content: String,
+}
+
+impl GitDiff {
+    pub(crate) fn new(file_path: impl Into<std::path::PathBuf>, content: String) -> Self {
+        GitDiff {
+            file_path: file_path.into(),
+            content,
+        }
+    }
+}
+
+/// Which changes to diff and review.
+#[derive(Debug, Clone)]
+pub(crate) enum DiffSource {
+    /// Unstaged working-tree changes (`git diff`).
+    Unstaged,
+    /// Staged changes (`git diff --cached`).
+    Staged,
+    /// A single commit, compared against its parent.
+    Commit(String),
+    /// The difference between the `base` and `head` trees (`git diff base..head`,
+    /// equivalent to `git diff base head`).
+    Range { base: String, head: String },
+}
+
+impl DiffSource {
+    /// Args for a single combined `git diff` covering every changed file at once.
+    pub(crate) fn combined_diff_args(&self) -> Vec<String> {
+        match self {
+            DiffSource::Unstaged => vec!["diff".to_string()],
+            DiffSource::Staged => vec!["diff".to_string(), "--cached".to_string()],
+            DiffSource::Commit(sha) => {
+                vec!["diff".to_string(), format!("{}^", sha), sha.clone()]
+            }
+            DiffSource::Range { base, head } => {
+                vec!["diff".to_string(), format!("{}..{}", base, head)]
+            }
+        }
+    }
+}
+
+/// How the review result should be rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OutputMode {
+    /// The human-readable markdown report the model produces by default.
+    Markdown,
+    /// Structured `ReviewReport` JSON, suitable for a CI gate.
+    Json,
 }
 
-struct CodeReviewer {
+/// A structured review result, used in `--output json` mode so callers can act on
+/// individual findings instead of parsing markdown.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ReviewReport {
+    pub(crate) summary: String,
+    pub(crate) files: Vec<FileFinding>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct FileFinding {
+    pub(crate) path: String,
+    pub(crate) breaking: bool,
+    pub(crate) severity: Severity,
+    pub(crate) impact: String,
+    pub(crate) suggestions: String,
+}
+
+impl ReviewReport {
+    /// The highest severity among all findings, or `None` if there are none.
+    pub(crate) fn max_severity(&self) -> Option<Severity> {
+        self.files.iter().map(|f| f.severity).max()
+    }
+}
+
+/// The result of a review, in whichever shape `OutputMode` asked for.
+pub(crate) enum ReviewOutcome {
+    Markdown(String),
+    Json(ReviewReport),
+}
+
+impl ReviewOutcome {
+    /// Renders the outcome as text, for printing or posting to a `Forge`.
+    pub(crate) fn as_text(&self) -> String {
+        match self {
+            ReviewOutcome::Markdown(text) => text.clone(),
+            ReviewOutcome::Json(report) => {
+                serde_json::to_string_pretty(report).unwrap_or_else(|_| report.summary.clone())
+            }
+        }
+    }
+
+    /// The highest finding severity, if this outcome carries structured findings.
+    pub(crate) fn max_severity(&self) -> Option<Severity> {
+        match self {
+            ReviewOutcome::Markdown(_) => None,
+            ReviewOutcome::Json(report) => report.max_severity(),
+        }
+    }
+}
+
+pub(crate) struct CodeReviewer {
     api_key: String,
-    repo_path: String,
+    git: git::Git,
+    config: RepoConfig,
 }
 
 impl CodeReviewer {
-    fn new(repo_path: String) -> Result<Self> {
+    pub(crate) fn new(repo_path: String) -> Result<Self> {
         let api_key = env::var("MOONSHOT_API_KEY")
             .context("MOONSHOT_API_KEY environment variable not set")?;
-        
+        let config = RepoConfig::load(&repo_path)?;
+
         Ok(CodeReviewer {
             api_key,
-            repo_path,
+            git: git::Git::new(repo_path),
+            config,
         })
     }
 
     fn validate_git_repository(&self) -> Result<()> {
-        let output = Command::new("git")
-            .arg("status")
-            .current_dir(&self.repo_path)
-            .output()
-            .context("Failed to execute git status. Make sure git is installed and the directory is a git repository")?;
-
-        if !output.status.success() {
-            anyhow::bail!("The directory '{}' is not a git repository", self.repo_path);
-        }
-
-        Ok(())
+        self.git.is_repository()
     }
 
-    fn get_unstaged_changes(&self) -> Result<Vec<GitDiff>> {
-        // Get list of modified files
-        let output = Command::new("git")
-            .args(&["diff", "--name-only"])
-            .current_dir(&self.repo_path)
-            .output()
-            .context("Failed to get list of modified files")?;
-
-        if !output.status.success() {
-            anyhow::bail!("Failed to get git diff: {}", String::from_utf8_lossy(&output.stderr));
-        }
-
-        let files = String::from_utf8(output.stdout)?;
-        let mut diffs = Vec::new();
-
-        for file_path in files.lines() {
-            if file_path.trim().is_empty() {
-                continue;
-            }
-
-            // Get the actual diff for this file
-            let diff_output = Command::new("git")
-                .args(&["diff", file_path])
-                .current_dir(&self.repo_path)
-                .output()
-                .context(format!("Failed to get diff for file: {}", file_path))?;
-
-            if diff_output.status.success() {
-                let diff_content = String::from_utf8(diff_output.stdout)?;
-                if !diff_content.trim().is_empty() {
-                    diffs.push(GitDiff {
-                        file_path: file_path.to_string(),
-                        content: diff_content,
-                    });
-                }
-            }
-        }
-
-        Ok(diffs)
+    pub(crate) fn get_diffs(&self, source: &DiffSource) -> Result<Vec<GitDiff>> {
+        Ok(self
+            .git
+            .diff(source)?
+            .into_iter()
+            .filter(|diff| !self.config.is_ignored(&diff.file_path.to_string_lossy()))
+            .collect())
     }
 
-    async fn analyze_with_kimi(&self, diffs: &[GitDiff]) -> Result<String> {
+    pub(crate) async fn analyze_with_kimi(
+        &self,
+        diffs: &[GitDiff],
+        output: OutputMode,
+    ) -> Result<String> {
         let client = reqwest::Client::new();
-        
+
         // Prepare the prompt for the LLM
-        let mut prompt = String::from(
-            "You are a senior code reviewer. Analyze the following git diffs to identify potential breaking changes that could affect the behavior of the software. \
-            For each change, determine:\n\
-            1. Whether it's a breaking change (yes/no)\n\
-            2. The severity (low/medium/high)\n\
-            3. What behavior might be affected\n\
-            4. Suggestions to prevent or mitigate the breaking change\n\n\
-            Please provide a structured analysis in the following format:\n\
-            ## Summary\n\
-            [Overall assessment]\n\n\
-            ## Detailed Analysis\n\
-            ### File: [filename]\n\
-            - **Breaking Change**: [yes/no]\n\
-            - **Severity**: [low/medium/high]\n\
-            - **Impact**: [description of what might break]\n\
-            - **Suggestions**: [how to prevent/mitigate]\n\n\
-            Here are the diffs to analyze:\n\n"
-        );
+        let mut prompt = match output {
+            OutputMode::Markdown => String::from(
+                "You are a senior code reviewer. Analyze the following git diffs to identify potential breaking changes that could affect the behavior of the software. \
+                For each change, determine:\n\
+                1. Whether it's a breaking change (yes/no)\n\
+                2. The severity (low/medium/high)\n\
+                3. What behavior might be affected\n\
+                4. Suggestions to prevent or mitigate the breaking change\n\n\
+                Please provide a structured analysis in the following format:\n\
+                ## Summary\n\
+                [Overall assessment]\n\n\
+                ## Detailed Analysis\n\
+                ### File: [filename]\n\
+                - **Breaking Change**: [yes/no]\n\
+                - **Severity**: [low/medium/high]\n\
+                - **Impact**: [description of what might break]\n\
+                - **Suggestions**: [how to prevent/mitigate]\n\n\
+                Here are the diffs to analyze:\n\n"
+            ),
+            OutputMode::Json => String::from(
+                "You are a senior code reviewer. Analyze the following git diffs to identify potential breaking changes that could affect the behavior of the software. \
+                Respond with ONLY a single JSON object matching this shape, and nothing else:\n\
+                {\"summary\": string, \"files\": [{\"path\": string, \"breaking\": bool, \"severity\": \"low\"|\"medium\"|\"high\", \"impact\": string, \"suggestions\": string}]}\n\n\
+                Here are the diffs to analyze:\n\n"
+            ),
+        };
 
         for diff in diffs {
-            prompt.push_str(&format!("### File: {}\n```diff\n{}\n```\n\n", diff.file_path, diff.content));
+            prompt.push_str(&format!(
+                "### File: {}\n```diff\n{}\n```\n\n",
+                diff.file_path.display(),
+                diff.content
+            ));
         }
 
         let body = json!({
-            "model": "kimi-k2-0711-preview",
+            "model": self.config.model.clone().unwrap_or_else(|| "kimi-k2-0711-preview".to_string()),
             "messages": [
                 {
                     "role": "system",
@@ -138,7 +217,7 @@ impl CodeReviewer {
                     "content": prompt
                 }
             ],
-            "temperature": 0.3
+            "temperature": self.config.temperature.unwrap_or(0.3)
         });
 
         let response = client
@@ -166,33 +245,84 @@ impl CodeReviewer {
         Ok(kimi_response.choices[0].message.content.clone())
     }
 
-    async fn review_changes(&self) -> Result<()> {
+    /// Reviews the changes from `source`, printing the analysis and returning it so
+    /// callers (e.g. posting to a `Forge`, or the `fail_on` CI gate) can act on it further.
+    pub(crate) async fn review_changes(
+        &self,
+        source: &DiffSource,
+        output: OutputMode,
+    ) -> Result<Option<ReviewOutcome>> {
         println!("🔍 Validating git repository...");
         self.validate_git_repository()?;
 
-        println!("📊 Getting unstaged changes...");
-        let diffs = self.get_unstaged_changes()?;
+        println!("📊 Getting changes...");
+        let diffs = self.get_diffs(source)?;
 
         if diffs.is_empty() {
-            println!("✅ No unstaged changes found in the repository.");
-            return Ok(());
+            println!("✅ No changes found for the selected diff source.");
+            return Ok(None);
         }
 
         println!("📝 Found {} modified file(s):", diffs.len());
         for diff in &diffs {
-            println!("  - {}", diff.file_path);
+            println!("  - {}", diff.file_path.display());
         }
 
         println!("\n🤖 Analyzing changes with AI...");
-        let analysis = self.analyze_with_kimi(&diffs).await?;
+        let analysis = self.analyze_with_kimi(&diffs, output).await?;
+
+        let outcome = match output {
+            OutputMode::Markdown => ReviewOutcome::Markdown(analysis),
+            OutputMode::Json => {
+                let report: ReviewReport = serde_json::from_str(strip_json_code_fence(&analysis))
+                    .context("Failed to parse the model's response as a ReviewReport")?;
+                ReviewOutcome::Json(report)
+            }
+        };
 
         println!("🔍 CODE REVIEW ANALYSIS");
         println!("{}", "=".repeat(80));
-        println!("{}", analysis);
+        println!("{}", outcome.as_text());
         println!("{}", "=".repeat(80));
 
-        Ok(())
+        Ok(Some(outcome))
+    }
+
+    pub(crate) fn fail_on(&self) -> Option<Severity> {
+        self.config.fail_on
+    }
+}
+
+/// Rejects a configured `fail_on` threshold unless `--output json` is selected, since
+/// severity findings can't be extracted from the markdown report to check against it.
+fn check_fail_on_requires_json(fail_on: Option<Severity>, output: OutputMode) -> Result<()> {
+    if fail_on.is_some() && output != OutputMode::Json {
+        anyhow::bail!(
+            "fail_on is configured in .codereviewer.toml, which requires --output json \
+            (severity findings can't be extracted from the markdown report)"
+        );
     }
+
+    Ok(())
+}
+
+/// Strips a surrounding ` ```json ... ``` ` (or plain ` ``` ... ``` `) code fence from a
+/// model response, if present, so `--output json` still parses when the model wraps its
+/// answer in one despite being asked for raw JSON.
+fn strip_json_code_fence(text: &str) -> &str {
+    let trimmed = text.trim();
+    let Some(without_open) = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+    else {
+        return trimmed;
+    };
+
+    without_open
+        .trim_start()
+        .strip_suffix("```")
+        .map(str::trim)
+        .unwrap_or(trimmed)
 }
 
 #[tokio::main]
@@ -206,8 +336,91 @@ async fn main() -> Result<()> {
                 .required(true)
                 .index(1)
         )
+        .arg(
+            Arg::new("forge")
+                .long("forge")
+                .help("Post the review back to a forge instead of only printing it (github, forgejo)")
+        )
+        .arg(
+            Arg::new("forge-repo")
+                .long("forge-repo")
+                .help("Repository the review is posted against, as owner/name")
+        )
+        .arg(
+            Arg::new("forge-ref")
+                .long("forge-ref")
+                .help("Commit SHA to attach the review comment to (posted as a commit comment; PR numbers are not supported)")
+        )
+        .arg(
+            Arg::new("forge-url")
+                .long("forge-url")
+                .help("Base URL of the forge instance (required for forgejo/gitea)")
+        )
+        .arg(
+            Arg::new("staged")
+                .long("staged")
+                .help("Review staged changes instead of unstaged ones")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["commit", "range"])
+        )
+        .arg(
+            Arg::new("commit")
+                .long("commit")
+                .help("Review a single commit, compared against its parent")
+                .conflicts_with_all(["staged", "range"])
+        )
+        .arg(
+            Arg::new("range")
+                .long("range")
+                .help("Review a commit range, given as base..head")
+                .conflicts_with_all(["staged", "commit"])
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .help("Output format: markdown (default) or json")
+                .default_value("markdown")
+        )
+        .subcommand(
+            ClapCommand::new("serve")
+                .about("Run a webhook server that reviews pushes as they arrive")
+                .arg(
+                    Arg::new("directory")
+                        .help("Path to the git repository to analyze")
+                        .required(true)
+                        .index(1)
+                )
+                .arg(
+                    Arg::new("port")
+                        .long("port")
+                        .help("Port to listen on")
+                        .default_value("8080")
+                )
+        )
         .get_matches();
 
+    if let Some(serve_matches) = matches.subcommand_matches("serve") {
+        let repo_path = serve_matches.get_one::<String>("directory").unwrap().clone();
+        let port: u16 = serve_matches
+            .get_one::<String>("port")
+            .unwrap()
+            .parse()
+            .context("Invalid --port value")?;
+
+        if !Path::new(&repo_path).is_dir() {
+            anyhow::bail!("Error: '{}' is not a valid directory", repo_path);
+        }
+
+        // Check if API key is set
+        if env::var("MOONSHOT_API_KEY").is_err() {
+            eprintln!("❌ Error: MOONSHOT_API_KEY environment variable is not set.");
+            eprintln!("Please set it with: export MOONSHOT_API_KEY=your_api_key_here");
+            std::process::exit(1);
+        }
+
+        return server::serve(repo_path, port).await;
+    }
+
     let repo_path = matches.get_one::<String>("directory").unwrap().clone();
 
     // Validate directory exists
@@ -223,14 +436,171 @@ async fn main() -> Result<()> {
     }
 
     println!("🚀 Starting code review for: {}", repo_path);
-    
+
+    let diff_source = if matches.get_flag("staged") {
+        DiffSource::Staged
+    } else if let Some(sha) = matches.get_one::<String>("commit") {
+        DiffSource::Commit(sha.clone())
+    } else if let Some(range) = matches.get_one::<String>("range") {
+        let (base, head) = range
+            .split_once("..")
+            .context("--range must be given as base..head")?;
+        DiffSource::Range {
+            base: base.to_string(),
+            head: head.to_string(),
+        }
+    } else {
+        DiffSource::Unstaged
+    };
+
+    let output_mode = match matches.get_one::<String>("output").map(String::as_str) {
+        Some("json") => OutputMode::Json,
+        Some("markdown") | None => OutputMode::Markdown,
+        Some(other) => anyhow::bail!("Unknown --output value: {} (expected markdown or json)", other),
+    };
+
     let reviewer = CodeReviewer::new(repo_path)?;
-    
-    if let Err(e) = reviewer.review_changes().await {
-        eprintln!("❌ Error: {}", e);
-        std::process::exit(1);
+
+    check_fail_on_requires_json(reviewer.fail_on(), output_mode)?;
+
+    let outcome = match reviewer.review_changes(&diff_source, output_mode).await {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            eprintln!("❌ Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if let (Some(outcome), Some(forge_name)) = (&outcome, matches.get_one::<String>("forge")) {
+        let kind = forge::ForgeKind::parse(forge_name)?;
+        let repo = matches
+            .get_one::<String>("forge-repo")
+            .context("--forge-repo is required when --forge is set")?
+            .clone();
+        let sha_or_pr = matches
+            .get_one::<String>("forge-ref")
+            .context("--forge-ref is required when --forge is set")?
+            .clone();
+        let token = env::var("FORGE_TOKEN").context("FORGE_TOKEN environment variable not set")?;
+        let base_url = matches.get_one::<String>("forge-url").cloned();
+
+        let forge = forge::build(kind, token, base_url)?;
+        forge.post_review(&repo, &sha_or_pr, &outcome.as_text()).await?;
+        println!("📮 Posted review to {} ({})", repo, sha_or_pr);
+    }
+
+    if let Some(outcome) = &outcome {
+        if let (Some(fail_on), Some(max_severity)) = (reviewer.fail_on(), outcome.max_severity()) {
+            if max_severity >= fail_on {
+                eprintln!(
+                    "❌ Review found a {:?} severity finding, at or above the configured fail_on threshold.",
+                    max_severity
+                );
+                std::process::exit(1);
+            }
+        }
     }
 
     println!("\n✅ Code review completed!");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_json_code_fence_strips_a_json_tagged_fence() {
+        let wrapped = "```json\n{\"summary\": \"ok\", \"files\": []}\n```";
+        assert_eq!(
+            strip_json_code_fence(wrapped),
+            "{\"summary\": \"ok\", \"files\": []}"
+        );
+    }
+
+    #[test]
+    fn strip_json_code_fence_strips_a_plain_fence() {
+        let wrapped = "```\n{\"summary\": \"ok\", \"files\": []}\n```";
+        assert_eq!(
+            strip_json_code_fence(wrapped),
+            "{\"summary\": \"ok\", \"files\": []}"
+        );
+    }
+
+    #[test]
+    fn strip_json_code_fence_passes_through_unfenced_json() {
+        let raw = "{\"summary\": \"ok\", \"files\": []}";
+        assert_eq!(strip_json_code_fence(raw), raw);
+    }
+
+    fn finding(severity: Severity) -> FileFinding {
+        FileFinding {
+            path: "src/main.rs".to_string(),
+            breaking: false,
+            severity,
+            impact: String::new(),
+            suggestions: String::new(),
+        }
+    }
+
+    #[test]
+    fn max_severity_returns_the_highest_finding_severity() {
+        let report = ReviewReport {
+            summary: String::new(),
+            files: vec![
+                finding(Severity::Low),
+                finding(Severity::High),
+                finding(Severity::Medium),
+            ],
+        };
+
+        assert_eq!(report.max_severity(), Some(Severity::High));
+    }
+
+    #[test]
+    fn max_severity_is_none_when_there_are_no_findings() {
+        let report = ReviewReport {
+            summary: String::new(),
+            files: vec![],
+        };
+
+        assert_eq!(report.max_severity(), None);
+    }
+
+    #[test]
+    fn check_fail_on_requires_json_passes_when_fail_on_is_unset() {
+        assert!(check_fail_on_requires_json(None, OutputMode::Markdown).is_ok());
+        assert!(check_fail_on_requires_json(None, OutputMode::Json).is_ok());
+    }
+
+    #[test]
+    fn check_fail_on_requires_json_passes_with_json_output() {
+        assert!(check_fail_on_requires_json(Some(Severity::High), OutputMode::Json).is_ok());
+    }
+
+    #[test]
+    fn check_fail_on_requires_json_bails_without_json_output() {
+        assert!(check_fail_on_requires_json(Some(Severity::High), OutputMode::Markdown).is_err());
+    }
+
+    #[test]
+    fn combined_diff_args_cover_every_diff_source() {
+        assert_eq!(DiffSource::Unstaged.combined_diff_args(), vec!["diff"]);
+        assert_eq!(
+            DiffSource::Staged.combined_diff_args(),
+            vec!["diff", "--cached"]
+        );
+        assert_eq!(
+            DiffSource::Commit("abc123".to_string()).combined_diff_args(),
+            vec!["diff", "abc123^", "abc123"]
+        );
+        assert_eq!(
+            DiffSource::Range {
+                base: "main".to_string(),
+                head: "feature".to_string()
+            }
+            .combined_diff_args(),
+            vec!["diff", "main..feature"]
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use std::env;
+
+use anyhow::{Context, Result};
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+
+use crate::{CodeReviewer, DiffSource, OutputMode};
+
+const WEBHOOK_SECRET_ENV: &str = "WEBHOOK_SECRET";
+const SIGNATURE_HEADER: &str = "x-hub-signature-256";
+
+#[derive(Debug, Deserialize)]
+struct PushPayload {
+    after: String,
+    repository: RepositoryInfo,
+}
+
+#[derive(Debug, Deserialize)]
+struct RepositoryInfo {
+    full_name: String,
+}
+
+#[derive(Clone)]
+struct ServerState {
+    repo_path: String,
+    webhook_secret: String,
+}
+
+/// Starts the webhook server, listening for forge push events on `/webhook`.
+pub async fn serve(repo_path: String, port: u16) -> Result<()> {
+    let webhook_secret =
+        env::var(WEBHOOK_SECRET_ENV).context("WEBHOOK_SECRET environment variable not set")?;
+
+    let state = ServerState {
+        repo_path,
+        webhook_secret,
+    };
+
+    let app = Router::new()
+        .route("/webhook", post(handle_webhook))
+        .with_state(state);
+
+    let addr = format!("0.0.0.0:{}", port);
+    println!("🚀 Listening for push webhooks on {}", addr);
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .with_context(|| format!("Failed to bind to {}", addr))?;
+
+    axum::serve(listener, app)
+        .await
+        .context("Webhook server stopped unexpectedly")?;
+
+    Ok(())
+}
+
+async fn handle_webhook(
+    State(state): State<ServerState>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> (StatusCode, String) {
+    if let Err(msg) = verify_signature(&headers, &body, &state.webhook_secret) {
+        return (StatusCode::UNAUTHORIZED, msg);
+    }
+
+    let payload: PushPayload = match serde_json::from_slice(&body) {
+        Ok(payload) => payload,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                format!("Failed to parse push payload: {}", e),
+            )
+        }
+    };
+
+    println!(
+        "📬 Received push to {} (tip {})",
+        payload.repository.full_name, payload.after
+    );
+
+    match review_commit(&state.repo_path, &payload.after).await {
+        Ok(()) => (StatusCode::OK, "review complete".to_string()),
+        Err(e) => {
+            eprintln!("❌ Error reviewing push: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        }
+    }
+}
+
+/// Verifies the `X-Hub-Signature-256: sha256=<hex>` header against `HMAC-SHA256(secret, body)`.
+fn verify_signature(headers: &HeaderMap, body: &[u8], secret: &str) -> Result<(), String> {
+    let header_value = headers
+        .get(SIGNATURE_HEADER)
+        .ok_or_else(|| "Missing X-Hub-Signature-256 header".to_string())?
+        .to_str()
+        .map_err(|_| "Invalid X-Hub-Signature-256 header".to_string())?;
+
+    let hex_sig = header_value
+        .strip_prefix("sha256=")
+        .ok_or_else(|| "X-Hub-Signature-256 header must start with sha256=".to_string())?;
+
+    let expected = hex::decode(hex_sig).map_err(|_| "Invalid signature encoding".to_string())?;
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|_| "Invalid webhook secret".to_string())?;
+    mac.update(body);
+    let computed = mac.finalize().into_bytes();
+
+    if computed.ct_eq(&expected).into() {
+        Ok(())
+    } else {
+        Err("Signature mismatch".to_string())
+    }
+}
+
+async fn review_commit(repo_path: &str, sha: &str) -> Result<()> {
+    let reviewer = CodeReviewer::new(repo_path.to_string())?;
+    let source = DiffSource::Commit(sha.to_string());
+    let diffs = reviewer.get_diffs(&source)?;
+
+    if diffs.is_empty() {
+        println!("✅ No changes found for commit {}.", sha);
+        return Ok(());
+    }
+
+    let analysis = reviewer.analyze_with_kimi(&diffs, OutputMode::Markdown).await?;
+    println!("🔍 CODE REVIEW ANALYSIS for {}", sha);
+    println!("{}", "=".repeat(80));
+    println!("{}", analysis);
+    println!("{}", "=".repeat(80));
+
+    Ok(())
+}